@@ -1,246 +1,1054 @@
-//! menu_rs is a library for Rust that allows the creation of simple and interactable command-line menus.
-//!
-//! It's very simple to use, you just create a Menu, adds the option you want it to have with the correspondent
-//! action to be run when selected and that's it!
-//! You can use the arrow keys to move through the options, ENTER to select an option and ESC to exit the menu.
-//!
-//! # Example
-//!
-//! ```
-//! use menu_rs::{Menu, MenuOption};
-//!
-//! fn action_1() {
-//!     println!("action 1")
-//! }
-//! fn action_2(val: u32) {
-//!     println!("action 2 with number {}", val)
-//! }
-//! fn action_3(msg: &str, val: f32) {
-//!     println!("action 3 with string {} and float {}", msg, val)
-//! }
-//! fn action_4() {
-//!     println!("action 4")
-//! }
-//!
-//! let menu = Menu::new(vec![
-//!     MenuOption::new("Option 1", action_1).hint("Hint for option 1"),
-//!     MenuOption::new("Option 2", || action_2(42)),
-//!     MenuOption::new("Option 3", || action_3("example", 3.14)),
-//!     MenuOption::new("Option 4", action_4),
-//! ]);
-//!
-//! menu.show();
-//! ```
-
-#![allow(clippy::needless_return)]
-#![allow(clippy::redundant_field_names)]
-
-use console::{Key, Style, Term};
-
-/// A option that can be added to a Menu.
-pub struct MenuOption {
-    label: String,
-    func: Box<dyn FnMut()>,
-    hint: Option<String>,
-}
-
-/// The Menu to be shown in the command line interface.
-pub struct Menu {
-    title: Option<String>,
-    options: Vec<MenuOption>,
-    selected_option: i32,
-    selected_style: Style,
-    normal_style: Style,
-    hint_style: Style,
-}
-
-impl MenuOption {
-    /// Creates a new Menu option that can then be used by a Menu.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// fn action_example() {}
-    /// let menu_option = MenuOption::new("Option example", action_example);
-    /// ```
-    pub fn new<F>(label: &str, func: F) -> MenuOption
-    where
-        F: FnMut() + 'static,
-    {
-        return MenuOption {
-            label: label.to_owned(),
-            func: Box::new(func),
-            hint: None,
-        };
-    }
-
-    /// Sets the hint label with the given text.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// fn action_1() {}
-    /// let menu_option_1 = MenuOption::new("Option 1", action_1).hint("Hint example");
-    /// ```
-    pub fn hint(mut self, text: &str) -> MenuOption {
-        self.hint = Some(text.to_owned());
-        return self;
-    }
-}
-
-impl Menu {
-    /// Creates a new interactable Menu.
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// fn action_example() {}
-    /// let menu_option = MenuOption::new("Option example", action_example);
-    /// let menu = Menu::new(vec![menu_option]);
-    /// ```
-    ///
-    /// You can use closures to easily use arguments in your functions.
-    ///
-    /// ```
-    /// fn action_example(msg: &str, val: f32) {
-    ///     println!("action 3 with string {} and float {}", msg, val)
-    /// }
-    /// let menu_option = MenuOption::new("Option example", || action_example("example", 3.514));
-    /// let menu = Menu::new(vec![menu_option]);
-    /// ```
-    pub fn new(options: Vec<MenuOption>) -> Menu {
-        return Menu {
-            title: None,
-            options: options,
-            selected_option: 0,
-            normal_style: Style::new(),
-            selected_style: Style::new().on_blue(),
-            hint_style: Style::new().color256(187),
-        };
-    }
-
-    /// Sets a title for the menu.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// fn action_example() {}
-    /// let menu_option = MenuOption::new("Option example", action_example);
-    /// let menu = Menu::new(vec![menu_option]).title("Title example");
-    /// ```
-    pub fn title(mut self, text: &str) -> Menu {
-        self.title = Some(text.to_owned());
-        return self;
-    }
-
-    /// Shows the menu in the command line interface allowing the user
-    /// to interact with the menu.
-    pub fn show(mut self) {
-        let stdout = Term::buffered_stdout();
-        stdout.hide_cursor().unwrap();
-
-        // clears the screen and shows the menu
-        stdout.clear_screen().unwrap();
-        self.draw_menu(&stdout);
-
-        // runs the menu navigation
-        self.menu_navigation(&stdout);
-
-        // clears the screen and runs the action function before exiting
-        stdout.clear_screen().unwrap();
-        stdout.flush().unwrap();
-
-        // return on exit selection
-        if self.selected_option == -1 {
-            return;
-        }
-
-        // runs the action function
-        let option = &mut self.options[self.selected_option as usize];
-        (option.func)();
-    }
-
-    fn menu_navigation(&mut self, stdout: &Term) {
-        let options_limit_num: i32 = (self.options.len() - 1) as i32;
-        loop {
-            // gets pressed key
-            let key = match stdout.read_key() {
-                Ok(val) => val,
-                Err(_e) => {
-                    println!("Error reading key");
-                    return;
-                }
-            };
-
-            // handles the pressed key
-            match key {
-                Key::ArrowUp => {
-                    self.selected_option = match self.selected_option == 0 {
-                        true => options_limit_num,
-                        false => self.selected_option - 1,
-                    }
-                }
-                Key::ArrowDown => {
-                    self.selected_option = match self.selected_option == options_limit_num {
-                        true => 0,
-                        false => self.selected_option + 1,
-                    }
-                }
-                Key::Escape => {
-                    self.selected_option = -1;
-                    stdout.show_cursor().unwrap();
-                    return;
-                }
-                Key::Enter => {
-                    stdout.show_cursor().unwrap();
-                    return;
-                }
-                // Key::Char(c) => println!("char {}", c),
-                _ => {}
-            }
-
-            // redraws the menu
-            self.draw_menu(stdout);
-        }
-    }
-
-    fn draw_menu(&self, stdout: &Term) {
-        // clears the screen
-        stdout.clear_screen().unwrap();
-
-        // draw title
-        match &self.title {
-            Some(text) => {
-                let title_style = Style::new().bold();
-                let title = title_style.apply_to(text);
-                let title = format!("  {}", title);
-                stdout.write_line(title.as_str()).unwrap()
-            }
-            None => {}
-        };
-
-        // draw the menu to stdout
-        for (i, option) in self.options.iter().enumerate() {
-            let option_idx: usize = self.selected_option as usize;
-            let label_style = match i == option_idx {
-                true => self.selected_style.clone(),
-                false => self.normal_style.clone(),
-            };
-
-            // styles the menu entry
-            let label = label_style.apply_to(option.label.as_str());
-            let hint_str = match &self.options[i].hint {
-                Some(hint) => hint,
-                None => "",
-            };
-            let hint = self.hint_style.apply_to(hint_str);
-
-            // builds and writes the menu entry
-            let line = format!("- {: <25}\t{}", label, hint);
-            stdout.write_line(line.as_str()).unwrap();
-        }
-
-        // draws to terminal
-        stdout.flush().unwrap();
-    }
-}
+//! menu_rs is a library for Rust that allows the creation of simple and interactable command-line menus.
+//!
+//! It's very simple to use, you just create a Menu, adds the option you want it to have with the correspondent
+//! action to be run when selected and that's it!
+//! You can use the arrow keys to move through the options, ENTER to select an option and ESC to exit the menu.
+//!
+//! # Example
+//!
+//! ```
+//! use menu_rs::{Menu, MenuOption};
+//!
+//! fn action_1() {
+//!     println!("action 1")
+//! }
+//! fn action_2(val: u32) {
+//!     println!("action 2 with number {}", val)
+//! }
+//! fn action_3(msg: &str, val: f32) {
+//!     println!("action 3 with string {} and float {}", msg, val)
+//! }
+//! fn action_4() {
+//!     println!("action 4")
+//! }
+//!
+//! let menu = Menu::new(vec![
+//!     MenuOption::new("Option 1", action_1).hint("Hint for option 1"),
+//!     MenuOption::new("Option 2", || action_2(42)),
+//!     MenuOption::new("Option 3", || action_3("example", 3.14)),
+//!     MenuOption::new("Option 4", action_4),
+//! ]);
+//!
+//! menu.show();
+//! ```
+
+#![allow(clippy::needless_return)]
+#![allow(clippy::redundant_field_names)]
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use console::{Key, Style, Term};
+
+/// The behavior that runs when a [`MenuOption`] is selected.
+enum MenuAction {
+    /// Runs a function and closes the whole menu tree.
+    Run(Box<dyn FnMut()>),
+    /// Opens a child [`Menu`], pushing it onto the navigation stack.
+    Submenu(Menu),
+    /// Holds a value that can be adjusted in place instead of running anything.
+    Value(MenuValue),
+    /// Returns to the parent menu. Appended automatically to a submenu's option
+    /// list by [`MenuOption::submenu`], alongside the existing ESC shortcut.
+    Back,
+}
+
+/// The value held by a value-selecting [`MenuOption`], adjusted in place while it
+/// is focused instead of running an action or opening a submenu.
+enum MenuValue {
+    /// Cycles through a fixed set of string values with ArrowLeft/ArrowRight.
+    Scroll { values: Vec<String>, index: usize },
+    /// A number adjusted by `step` with ArrowLeft/ArrowRight, optionally bounded.
+    Numeric {
+        value: f64,
+        min: Option<f64>,
+        max: Option<f64>,
+        step: f64,
+    },
+    /// Free-form text built up from typed characters, trimmed with Backspace.
+    String(String),
+}
+
+impl MenuValue {
+    /// Renders the current value as the string that `value_of` should return.
+    fn as_string(&self) -> String {
+        return match self {
+            MenuValue::Scroll { values, index } => match values.get(*index) {
+                Some(value) => value.clone(),
+                None => String::new(),
+            },
+            MenuValue::Numeric { value, .. } => format_numeric(*value),
+            MenuValue::String(text) => text.clone(),
+        };
+    }
+
+    /// Moves the value one step in `direction` (negative for left, positive for right).
+    fn shift(&mut self, direction: i32) {
+        match self {
+            MenuValue::Scroll { values, index } => {
+                if values.is_empty() {
+                    return;
+                }
+                let len = values.len() as i32;
+                *index = (*index as i32 + direction).rem_euclid(len) as usize;
+            }
+            MenuValue::Numeric {
+                value,
+                min,
+                max,
+                step,
+            } => {
+                let mut new_value = *value + (*step * direction as f64);
+                if let Some(min) = min {
+                    new_value = new_value.max(*min);
+                }
+                if let Some(max) = max {
+                    new_value = new_value.min(*max);
+                }
+                *value = new_value;
+            }
+            MenuValue::String(_) => {}
+        }
+    }
+}
+
+/// The index, label and (if any) action function of the option that was chosen
+/// when a menu's navigation loop exits without being cancelled.
+struct MenuSelection {
+    index: usize,
+    label: String,
+    func: Option<Box<dyn FnMut()>>,
+}
+
+/// The outcome of running a [`Menu`] with [`Menu::run`].
+pub struct MenuResult {
+    /// The index of the selected option, or `None` if the menu was cancelled.
+    pub selected: Option<usize>,
+    /// The label of the selected option, or `None` if the menu was cancelled.
+    pub label: Option<String>,
+    /// Whether the user cancelled the menu with ESC instead of selecting an option.
+    pub cancelled: bool,
+}
+
+/// A option that can be added to a Menu.
+pub struct MenuOption {
+    label: String,
+    action: MenuAction,
+    hint: Option<String>,
+    hotkey: Option<char>,
+    preview: Option<Box<dyn FnMut() -> String>>,
+}
+
+/// The Menu to be shown in the command line interface.
+pub struct Menu {
+    title: Option<String>,
+    options: Vec<MenuOption>,
+    selected_option: i32,
+    scroll_offset: i32,
+    selected_style: Style,
+    normal_style: Style,
+    hint_style: Style,
+    external: Option<ExternalBackend>,
+}
+
+/// An external filter program (dmenu/rofi/fzf, ...) used to render a [`Menu`]
+/// instead of drawing it with `console::Term`.
+#[derive(Clone)]
+struct ExternalBackend {
+    command: String,
+    args: Vec<String>,
+}
+
+impl MenuOption {
+    /// Creates a new Menu option that can then be used by a Menu.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn action_example() {}
+    /// let menu_option = MenuOption::new("Option example", action_example);
+    /// ```
+    pub fn new<F>(label: &str, func: F) -> MenuOption
+    where
+        F: FnMut() + 'static,
+    {
+        return MenuOption {
+            label: label.to_owned(),
+            action: MenuAction::Run(Box::new(func)),
+            hint: None,
+            hotkey: None,
+            preview: None,
+        };
+    }
+
+    /// Creates a new Menu option that opens a child Menu instead of running an action.
+    ///
+    /// Pressing ENTER on this option navigates into `menu`; pressing ESC, or selecting
+    /// the "< Back" entry appended to `menu` automatically, returns to the parent menu
+    /// instead of closing the whole menu tree.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use menu_rs::{Menu, MenuOption};
+    ///
+    /// fn action_example() {}
+    /// let submenu = Menu::new(vec![MenuOption::new("Suboption", action_example)]);
+    /// let menu_option = MenuOption::submenu("Submenu example", submenu);
+    /// ```
+    pub fn submenu(label: &str, mut menu: Menu) -> MenuOption {
+        menu.options.push(MenuOption {
+            label: "< Back".to_owned(),
+            action: MenuAction::Back,
+            hint: None,
+            hotkey: None,
+            preview: None,
+        });
+        return MenuOption {
+            label: label.to_owned(),
+            action: MenuAction::Submenu(menu),
+            hint: None,
+            hotkey: None,
+            preview: None,
+        };
+    }
+
+    /// Creates a new Menu option that cycles through `values` with ArrowLeft/ArrowRight
+    /// instead of running an action. The current value is read back with
+    /// [`Menu::value_of`] after the menu exits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use menu_rs::MenuOption;
+    ///
+    /// let menu_option = MenuOption::scroll("Resolution", vec!["720p", "1080p", "4K"]);
+    /// ```
+    pub fn scroll(label: &str, values: Vec<&str>) -> MenuOption {
+        return MenuOption {
+            label: label.to_owned(),
+            action: MenuAction::Value(MenuValue::Scroll {
+                values: values.into_iter().map(|value| value.to_owned()).collect(),
+                index: 0,
+            }),
+            hint: None,
+            hotkey: None,
+            preview: None,
+        };
+    }
+
+    /// Creates a new Menu option holding a number adjusted with ArrowLeft/ArrowRight
+    /// instead of running an action, starting at `value`. Use [`MenuOption::min`],
+    /// [`MenuOption::max`] and [`MenuOption::step`] to configure how it's adjusted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use menu_rs::MenuOption;
+    ///
+    /// let menu_option = MenuOption::numeric("Volume", 50.0).min(0.0).max(100.0).step(5.0);
+    /// ```
+    pub fn numeric(label: &str, value: f64) -> MenuOption {
+        return MenuOption {
+            label: label.to_owned(),
+            action: MenuAction::Value(MenuValue::Numeric {
+                value,
+                min: None,
+                max: None,
+                step: 1.0,
+            }),
+            hint: None,
+            hotkey: None,
+            preview: None,
+        };
+    }
+
+    /// Creates a new Menu option holding free-form text built up from typed characters
+    /// instead of running an action, starting at `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use menu_rs::MenuOption;
+    ///
+    /// let menu_option = MenuOption::string("Username", "");
+    /// ```
+    pub fn string(label: &str, value: &str) -> MenuOption {
+        return MenuOption {
+            label: label.to_owned(),
+            action: MenuAction::Value(MenuValue::String(value.to_owned())),
+            hint: None,
+            hotkey: None,
+            preview: None,
+        };
+    }
+
+    /// Sets the lower bound for a [`MenuOption::numeric`] item. Has no effect on
+    /// other option types.
+    pub fn min(mut self, min: f64) -> MenuOption {
+        if let MenuAction::Value(MenuValue::Numeric { min: bound, .. }) = &mut self.action {
+            *bound = Some(min);
+        }
+        return self;
+    }
+
+    /// Sets the upper bound for a [`MenuOption::numeric`] item. Has no effect on
+    /// other option types.
+    pub fn max(mut self, max: f64) -> MenuOption {
+        if let MenuAction::Value(MenuValue::Numeric { max: bound, .. }) = &mut self.action {
+            *bound = Some(max);
+        }
+        return self;
+    }
+
+    /// Sets the increment used by ArrowLeft/ArrowRight for a [`MenuOption::numeric`]
+    /// item. Has no effect on other option types.
+    pub fn step(mut self, step: f64) -> MenuOption {
+        if let MenuAction::Value(MenuValue::Numeric { step: s, .. }) = &mut self.action {
+            *s = step;
+        }
+        return self;
+    }
+
+    /// Sets the hint label with the given text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn action_1() {}
+    /// let menu_option_1 = MenuOption::new("Option 1", action_1).hint("Hint example");
+    /// ```
+    pub fn hint(mut self, text: &str) -> MenuOption {
+        self.hint = Some(text.to_owned());
+        return self;
+    }
+
+    /// Sets a hotkey that instantly selects and confirms this option when pressed,
+    /// without needing to navigate to it with the arrow keys first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn action_1() {}
+    /// let menu_option_1 = MenuOption::new("Option 1", action_1).hotkey('a');
+    /// ```
+    pub fn hotkey(mut self, key: char) -> MenuOption {
+        self.hotkey = Some(key);
+        return self;
+    }
+
+    /// Sets a preview callback that's run to produce the text shown below the menu
+    /// while this option is highlighted, so the user can see context (file contents,
+    /// a computed summary, ...) before selecting it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn action_1() {}
+    /// let menu_option_1 = MenuOption::new("Option 1", action_1)
+    ///     .preview(|| "Preview for option 1".to_owned());
+    /// ```
+    pub fn preview<F>(mut self, preview: F) -> MenuOption
+    where
+        F: FnMut() -> String + 'static,
+    {
+        self.preview = Some(Box::new(preview));
+        return self;
+    }
+}
+
+impl Menu {
+    /// Creates a new interactable Menu.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// fn action_example() {}
+    /// let menu_option = MenuOption::new("Option example", action_example);
+    /// let menu = Menu::new(vec![menu_option]);
+    /// ```
+    ///
+    /// You can use closures to easily use arguments in your functions.
+    ///
+    /// ```
+    /// fn action_example(msg: &str, val: f32) {
+    ///     println!("action 3 with string {} and float {}", msg, val)
+    /// }
+    /// let menu_option = MenuOption::new("Option example", || action_example("example", 3.514));
+    /// let menu = Menu::new(vec![menu_option]);
+    /// ```
+    pub fn new(options: Vec<MenuOption>) -> Menu {
+        return Menu {
+            title: None,
+            options: options,
+            selected_option: 0,
+            scroll_offset: 0,
+            normal_style: Style::new(),
+            selected_style: Style::new().on_blue(),
+            hint_style: Style::new().color256(187),
+            external: None,
+        };
+    }
+
+    /// Renders this menu with an external filter program (e.g. `dmenu`, `rofi --dmenu`
+    /// or `fzf`) instead of drawing it with `console::Term`. Each option's label is
+    /// written to the program's stdin, one per line, and the chosen line is read back
+    /// from its stdout to resolve the selection. A non-zero exit status or empty
+    /// output is treated the same as cancelling with ESC.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use menu_rs::{Menu, MenuOption};
+    ///
+    /// fn action_example() {}
+    /// let menu = Menu::new(vec![MenuOption::new("Option example", action_example)])
+    ///     .with_external("fzf", vec![]);
+    /// ```
+    pub fn with_external(mut self, command: &str, args: Vec<&str>) -> Menu {
+        self.external = Some(ExternalBackend {
+            command: command.to_owned(),
+            args: args.into_iter().map(|arg| arg.to_owned()).collect(),
+        });
+        return self;
+    }
+
+    /// Sets a title for the menu.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// fn action_example() {}
+    /// let menu_option = MenuOption::new("Option example", action_example);
+    /// let menu = Menu::new(vec![menu_option]).title("Title example");
+    /// ```
+    pub fn title(mut self, text: &str) -> Menu {
+        self.title = Some(text.to_owned());
+        return self;
+    }
+
+    /// Shows the menu in the command line interface, runs the action function of
+    /// the selected option and exits. A convenience alternative to [`Menu::run`]
+    /// for callers that don't need to inspect the selection themselves.
+    pub fn show(mut self) {
+        let selection = self.navigate(true);
+        if let Some(mut selection) = selection {
+            if let Some(mut func) = selection.func.take() {
+                func();
+            }
+        }
+    }
+
+    /// Shows the menu and returns the chosen result, without running any action.
+    /// This lets callers inspect the selection, re-show the same menu in a loop,
+    /// or defer side effects instead of having them run automatically.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use menu_rs::{Menu, MenuOption};
+    ///
+    /// fn action_example() {}
+    /// let mut menu = Menu::new(vec![MenuOption::new("Option example", action_example)]);
+    /// let result = menu.run();
+    /// if !result.cancelled {
+    ///     println!("selected {:?}", result.label);
+    /// }
+    /// ```
+    pub fn run(&mut self) -> MenuResult {
+        // doesn't take the selected option's action out, unlike `show`, so the
+        // menu can still be `show`n (or `run` again) afterwards with its actions intact
+        return match self.navigate(false) {
+            Some(selection) => MenuResult {
+                selected: Some(selection.index),
+                label: Some(selection.label),
+                cancelled: false,
+            },
+            None => MenuResult {
+                selected: None,
+                label: None,
+                cancelled: true,
+            },
+        };
+    }
+
+    /// Returns the current value of the value-selecting option (see
+    /// [`MenuOption::scroll`], [`MenuOption::numeric`] and [`MenuOption::string`])
+    /// with the given `label`, or `None` if there's no such option, or it isn't a
+    /// value-selecting one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use menu_rs::{Menu, MenuOption};
+    ///
+    /// let mut menu = Menu::new(vec![MenuOption::scroll("Resolution", vec!["720p", "1080p"])]);
+    /// menu.run();
+    /// let resolution = menu.value_of("Resolution");
+    /// ```
+    pub fn value_of(&self, label: &str) -> Option<String> {
+        let option = self.options.iter().find(|option| option.label == label)?;
+        return match &option.action {
+            MenuAction::Value(value) => Some(value.as_string()),
+            _ => None,
+        };
+    }
+
+    /// Draws the menu, runs the navigation loop and tears down the terminal state,
+    /// returning the selection (if any) for `show`/`run` to interpret. Only takes
+    /// the selected option's action out of the menu (so it can be run) when
+    /// `take_action` is set; `run()` passes `false` so the menu's actions survive
+    /// for a later `show`/`run` call.
+    fn navigate(&mut self, take_action: bool) -> Option<MenuSelection> {
+        if self.external.is_some() {
+            return self.navigate_external(take_action);
+        }
+
+        // resets the cursor in case it was left at -1 by an ESC from a previous
+        // `run()`/`show()` call, which would otherwise panic on the next ENTER
+        // (self.options[usize::MAX]) instead of redrawing the first option
+        self.selected_option = 0;
+        self.scroll_offset = 0;
+
+        let stdout = Term::buffered_stdout();
+        stdout.hide_cursor().unwrap();
+
+        // clears the screen and shows the menu
+        stdout.clear_screen().unwrap();
+        self.draw_menu(&stdout);
+
+        // runs the menu navigation, collecting the selection (if any) from
+        // whichever menu in the submenu stack was ultimately chosen
+        let selection = self.menu_navigation(&stdout, take_action);
+
+        // clears the screen before handing control back
+        stdout.clear_screen().unwrap();
+        stdout.flush().unwrap();
+
+        return selection;
+    }
+
+    /// Renders this menu through its configured external filter program instead of
+    /// `console::Term`, writing each option's label to its stdin and resolving the
+    /// selection from whichever line it writes back to stdout.
+    fn navigate_external(&mut self, take_action: bool) -> Option<MenuSelection> {
+        let backend = self.external.clone()?;
+
+        let mut child = Command::new(&backend.command)
+            .args(&backend.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .ok()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            for option in &self.options {
+                // a filter like fzf may close its stdin as soon as the user picks,
+                // so a write error here just means "stop feeding it", not "cancelled"
+                if writeln!(stdin, "{}", option.label).is_err() {
+                    break;
+                }
+            }
+        }
+
+        let output = child.wait_with_output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let chosen = stdout.lines().next().unwrap_or("").trim();
+        if chosen.is_empty() {
+            return None;
+        }
+
+        let idx = self
+            .options
+            .iter()
+            .position(|option| option.label == chosen)?;
+        match &self.options[idx].action {
+            MenuAction::Submenu(_) => {
+                let child_menu = match &mut self.options[idx].action {
+                    MenuAction::Submenu(menu) => menu,
+                    MenuAction::Run(_) | MenuAction::Value(_) | MenuAction::Back => {
+                        unreachable!()
+                    }
+                };
+                // only recurse if the submenu has its own external backend, since
+                // there's no interactive fallback to draw it with otherwise
+                if child_menu.external.is_some() {
+                    child_menu.navigate_external(take_action)
+                } else {
+                    None
+                }
+            }
+            MenuAction::Run(_) => {
+                let label = self.options[idx].label.clone();
+                let func = if take_action {
+                    let taken = std::mem::replace(
+                        &mut self.options[idx].action,
+                        MenuAction::Run(Box::new(|| {})),
+                    );
+                    match taken {
+                        MenuAction::Run(func) => Some(func),
+                        MenuAction::Submenu(_) | MenuAction::Value(_) | MenuAction::Back => {
+                            unreachable!()
+                        }
+                    }
+                } else {
+                    None
+                };
+                Some(MenuSelection {
+                    index: idx,
+                    label,
+                    func,
+                })
+            }
+            MenuAction::Value(_) => Some(MenuSelection {
+                index: idx,
+                label: self.options[idx].label.clone(),
+                func: None,
+            }),
+            // there's no parent navigation loop to return to in external mode, so
+            // selecting "< Back" is the same as cancelling the whole menu tree
+            MenuAction::Back => None,
+        }
+    }
+
+    /// Runs the navigation loop for this menu, recursing into child menus when a
+    /// submenu option is selected. Returns the option that was ultimately chosen,
+    /// or `None` if the user cancelled with ESC.
+    fn menu_navigation(&mut self, stdout: &Term, take_action: bool) -> Option<MenuSelection> {
+        let options_limit_num: i32 = (self.options.len() - 1) as i32;
+        loop {
+            // gets pressed key
+            let key = match stdout.read_key() {
+                Ok(val) => val,
+                Err(_e) => {
+                    println!("Error reading key");
+                    return None;
+                }
+            };
+
+            // handles the pressed key
+            match key {
+                Key::ArrowUp => {
+                    self.selected_option = match self.selected_option == 0 {
+                        true => options_limit_num,
+                        false => self.selected_option - 1,
+                    };
+                    self.update_scroll(stdout);
+                }
+                Key::ArrowDown => {
+                    self.selected_option = match self.selected_option == options_limit_num {
+                        true => 0,
+                        false => self.selected_option + 1,
+                    };
+                    self.update_scroll(stdout);
+                }
+                Key::Escape => {
+                    self.selected_option = -1;
+                    stdout.show_cursor().unwrap();
+                    return None;
+                }
+                Key::Enter => {
+                    let idx = self.selected_option as usize;
+                    if let Some(result) = self.activate_option(idx, stdout, take_action) {
+                        return result;
+                    }
+                }
+                Key::ArrowLeft => {
+                    let idx = self.selected_option as usize;
+                    if let MenuAction::Value(value) = &mut self.options[idx].action {
+                        value.shift(-1);
+                    }
+                }
+                Key::ArrowRight => {
+                    let idx = self.selected_option as usize;
+                    if let MenuAction::Value(value) = &mut self.options[idx].action {
+                        value.shift(1);
+                    }
+                }
+                Key::Backspace => {
+                    let idx = self.selected_option as usize;
+                    if let MenuAction::Value(MenuValue::String(text)) =
+                        &mut self.options[idx].action
+                    {
+                        text.pop();
+                    }
+                }
+                Key::Char(c) => {
+                    let idx = self.selected_option as usize;
+                    if let MenuAction::Value(MenuValue::String(text)) =
+                        &mut self.options[idx].action
+                    {
+                        // typing into a focused string item takes priority over hotkeys
+                        text.push(c);
+                    } else {
+                        let hotkey_idx = self
+                            .options
+                            .iter()
+                            .position(|option| option.hotkey == Some(c));
+
+                        if let Some(idx) = hotkey_idx {
+                            self.selected_option = idx as i32;
+                            self.update_scroll(stdout);
+                            if let Some(result) = self.activate_option(idx, stdout, take_action) {
+                                return result;
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            // redraws the menu
+            self.draw_menu(stdout);
+        }
+    }
+
+    /// Selects and confirms the option at `idx`, as if the user had navigated to it
+    /// and pressed ENTER. Returns `Some` with the outcome of the selection (to be
+    /// returned from `menu_navigation`), or `None` if the menu should keep running
+    /// (e.g. a submenu was entered and then cancelled back out of).
+    fn activate_option(
+        &mut self,
+        idx: usize,
+        stdout: &Term,
+        take_action: bool,
+    ) -> Option<Option<MenuSelection>> {
+        return match &self.options[idx].action {
+            MenuAction::Submenu(_) => {
+                // enters the submenu, reusing this menu's own navigation
+                // so ESC inside the child simply returns here
+                let result = {
+                    let child = match &mut self.options[idx].action {
+                        MenuAction::Submenu(menu) => menu,
+                        MenuAction::Run(_) | MenuAction::Value(_) | MenuAction::Back => {
+                            unreachable!()
+                        }
+                    };
+                    // resets the child's cursor in case it was left at -1 by an
+                    // ESC from a previous visit, which would otherwise panic on
+                    // the next ENTER (self.options[usize::MAX])
+                    child.selected_option = 0;
+                    child.scroll_offset = 0;
+
+                    stdout.clear_screen().unwrap();
+                    child.draw_menu(stdout);
+                    child.menu_navigation(stdout, take_action)
+                };
+
+                // a selection was made somewhere down the stack, so bubble
+                // it all the way up instead of redrawing this menu
+                if result.is_some() {
+                    stdout.show_cursor().unwrap();
+                    return Some(result);
+                }
+                None
+            }
+            MenuAction::Run(_) => {
+                stdout.show_cursor().unwrap();
+                let label = self.options[idx].label.clone();
+                // only moves the closure out when the caller will actually run it
+                // (`show`); `run` just inspects the selection and leaves the menu's
+                // actions intact for a later `show`/`run` call
+                let func = if take_action {
+                    let taken = std::mem::replace(
+                        &mut self.options[idx].action,
+                        MenuAction::Run(Box::new(|| {})),
+                    );
+                    match taken {
+                        MenuAction::Run(func) => Some(func),
+                        MenuAction::Submenu(_) | MenuAction::Value(_) | MenuAction::Back => {
+                            unreachable!()
+                        }
+                    }
+                } else {
+                    None
+                };
+                Some(Some(MenuSelection {
+                    index: idx,
+                    label,
+                    func,
+                }))
+            }
+            // value items are adjusted with ArrowLeft/ArrowRight (or typed into, for
+            // strings) instead of being activated, so ENTER on one is a no-op
+            MenuAction::Value(_) => None,
+            // acts like ESC: stops this menu's navigation loop with no selection,
+            // so the parent's `activate_option` sees it return and keeps running
+            MenuAction::Back => {
+                stdout.show_cursor().unwrap();
+                Some(None)
+            }
+        };
+    }
+
+    /// Returns how many options can be drawn at once given the terminal height,
+    /// leaving room for the title (if any) and the scroll indicators.
+    fn visible_option_rows(&self, stdout: &Term) -> i32 {
+        let (rows, _cols) = stdout.size();
+        let title_rows: i32 = if self.title.is_some() { 1 } else { 0 };
+        return (rows as i32 - title_rows - 2).max(1);
+    }
+
+    /// Keeps `scroll_offset` such that `selected_option` stays within the visible
+    /// viewport, scrolling down when the cursor would pass the bottom row and up
+    /// when it would pass the top row.
+    fn update_scroll(&mut self, stdout: &Term) {
+        let visible_rows = self.visible_option_rows(stdout);
+
+        if self.selected_option < self.scroll_offset {
+            self.scroll_offset = self.selected_option;
+        } else if self.selected_option >= self.scroll_offset + visible_rows {
+            self.scroll_offset = self.selected_option - visible_rows + 1;
+        }
+
+        let max_offset = (self.options.len() as i32 - visible_rows).max(0);
+        self.scroll_offset = self.scroll_offset.clamp(0, max_offset);
+    }
+
+    fn draw_menu(&mut self, stdout: &Term) {
+        // clears the screen
+        stdout.clear_screen().unwrap();
+
+        // draw title
+        match &self.title {
+            Some(text) => {
+                let title_style = Style::new().bold();
+                let title = title_style.apply_to(text);
+                let title = format!("  {}", title);
+                stdout.write_line(title.as_str()).unwrap()
+            }
+            None => {}
+        };
+
+        // draw only the slice of options that fits the terminal height, with a
+        // small indicator when content is clipped above/below
+        let visible_rows = self.visible_option_rows(stdout) as usize;
+        let start = self.scroll_offset as usize;
+        let end = (start + visible_rows).min(self.options.len());
+
+        if start > 0 {
+            stdout.write_line(&format!("  ▲ {} more", start)).unwrap();
+        }
+
+        for (i, option) in self
+            .options
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(end - start)
+        {
+            let option_idx: usize = self.selected_option as usize;
+            let label_style = match i == option_idx {
+                true => self.selected_style.clone(),
+                false => self.normal_style.clone(),
+            };
+
+            // styles the menu entry, marking submenu entries so the user knows
+            // ENTER will navigate into a child menu instead of running an action,
+            // and prefixing the hotkey (if any) so the user knows which key to press
+            let mut label_text = match &option.action {
+                MenuAction::Submenu(_) => format!("{} ->", option.label),
+                MenuAction::Run(_) | MenuAction::Back => option.label.clone(),
+                MenuAction::Value(MenuValue::String(text)) => {
+                    format!("{}: {}_", option.label, text)
+                }
+                MenuAction::Value(value) => format!("{}: < {} >", option.label, value.as_string()),
+            };
+            if let Some(hotkey) = option.hotkey {
+                label_text = format!("[{}] {}", hotkey, label_text);
+            }
+            let label = label_style.apply_to(label_text);
+            let hint_str = match &self.options[i].hint {
+                Some(hint) => hint,
+                None => "",
+            };
+            let hint = self.hint_style.apply_to(hint_str);
+
+            // builds and writes the menu entry
+            let line = format!("- {: <25}\t{}", label, hint);
+            stdout.write_line(line.as_str()).unwrap();
+        }
+
+        let below = self.options.len() - end;
+        if below > 0 {
+            stdout.write_line(&format!("  ▼ {} more", below)).unwrap();
+        }
+
+        // draws a preview pane below the menu for the highlighted option, if it has one
+        let option_idx = self.selected_option as usize;
+        if let Some(option) = self.options.get_mut(option_idx) {
+            if let Some(preview) = &mut option.preview {
+                let (_, cols) = stdout.size();
+                let text = preview();
+
+                stdout.write_line("").unwrap();
+                for line in wrap_preview(&text, cols as usize) {
+                    stdout.write_line(&line).unwrap();
+                }
+            }
+        }
+
+        // draws to terminal
+        stdout.flush().unwrap();
+    }
+}
+
+/// Wraps `text` to `width` columns, preserving existing line breaks and cutting
+/// any single word wider than `width`, so a preview pane never writes lines
+/// longer than the terminal can show.
+fn wrap_preview(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        if paragraph.is_empty() {
+            lines.push(String::new());
+            continue;
+        }
+
+        let mut current = String::new();
+        for word in paragraph.split_whitespace() {
+            if current.is_empty() {
+                current = word.to_owned();
+            } else if current.len() + 1 + word.len() <= width {
+                current.push(' ');
+                current.push_str(word);
+            } else {
+                lines.push(current);
+                current = word.to_owned();
+            }
+        }
+        lines.push(current);
+    }
+
+    // a single word wider than the pane can't be wrapped, so it's cut to fit
+    for line in &mut lines {
+        if let Some((cut, _)) = line.char_indices().nth(width) {
+            line.truncate(cut);
+        }
+    }
+
+    return lines;
+}
+
+/// Formats a numeric value for display and for [`Menu::value_of`], rounding away
+/// the float accumulation noise repeated `shift` calls can introduce (e.g.
+/// `0.1 + 0.2` rendering as `"0.30000000000000004"`).
+fn format_numeric(value: f64) -> String {
+    let rounded = (value * 1e9).round() / 1e9;
+    let text = format!("{:.9}", rounded);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text.is_empty() || text == "-" {
+        return "0".to_owned();
+    }
+    return text.to_owned();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn submenu_gets_a_back_entry_appended() {
+        let submenu = Menu::new(vec![MenuOption::new("Suboption", || {})]);
+        let option = MenuOption::submenu("Submenu", submenu);
+        match &option.action {
+            MenuAction::Submenu(menu) => {
+                assert_eq!(menu.options.len(), 2);
+                assert_eq!(menu.options[1].label, "< Back");
+                assert!(matches!(menu.options[1].action, MenuAction::Back));
+            }
+            _ => panic!("expected a submenu action"),
+        }
+    }
+
+    #[test]
+    fn scroll_shift_wraps_around() {
+        let mut value = MenuValue::Scroll {
+            values: vec!["720p".to_owned(), "1080p".to_owned(), "4K".to_owned()],
+            index: 0,
+        };
+        value.shift(-1);
+        assert_eq!(value.as_string(), "4K");
+        value.shift(1);
+        assert_eq!(value.as_string(), "720p");
+    }
+
+    #[test]
+    fn scroll_shift_and_as_string_guard_empty_values() {
+        let mut value = MenuValue::Scroll {
+            values: vec![],
+            index: 0,
+        };
+        value.shift(1);
+        assert_eq!(value.as_string(), "");
+    }
+
+    #[test]
+    fn numeric_shift_clamps_to_bounds() {
+        let mut value = MenuValue::Numeric {
+            value: 95.0,
+            min: Some(0.0),
+            max: Some(100.0),
+            step: 10.0,
+        };
+        value.shift(1);
+        assert_eq!(value.as_string(), "100");
+        value.shift(-20);
+        assert_eq!(value.as_string(), "0");
+    }
+
+    #[test]
+    fn numeric_as_string_rounds_float_noise() {
+        let mut value = MenuValue::Numeric {
+            value: 0.1,
+            min: None,
+            max: None,
+            step: 0.2,
+        };
+        value.shift(1);
+        assert_eq!(value.as_string(), "0.3");
+    }
+
+    #[test]
+    fn value_of_reads_back_the_current_value() {
+        let mut menu = Menu::new(vec![MenuOption::scroll(
+            "Resolution",
+            vec!["720p", "1080p"],
+        )]);
+        if let MenuAction::Value(value) = &mut menu.options[0].action {
+            value.shift(1);
+        }
+        assert_eq!(menu.value_of("Resolution"), Some("1080p".to_owned()));
+        assert_eq!(menu.value_of("Missing"), None);
+    }
+
+    #[test]
+    fn wrap_preview_breaks_long_paragraphs_and_keeps_existing_newlines() {
+        let wrapped = wrap_preview("one two three\nfour", 7);
+        assert_eq!(wrapped, vec!["one two", "three", "four"]);
+    }
+
+    #[test]
+    fn update_scroll_follows_the_selected_option_down_and_up() {
+        // enough options that no plausible terminal height shows them all at once
+        let mut menu = Menu::new(
+            (0..200)
+                .map(|i| MenuOption::new(&format!("Option {}", i), || {}))
+                .collect(),
+        );
+        let stdout = Term::stdout();
+
+        menu.selected_option = 199;
+        menu.update_scroll(&stdout);
+        assert!(menu.scroll_offset > 0);
+
+        menu.selected_option = 0;
+        menu.update_scroll(&stdout);
+        assert_eq!(menu.scroll_offset, 0);
+    }
+}